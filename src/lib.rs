@@ -21,6 +21,11 @@ pub enum ArgParseError {
     HelpRequested,
     /// A required argument was not provided.
     MissingRequiredArgument(String),
+    /// An option was given a value outside its `possible_values` set.
+    InvalidValue { option: String, value: String, allowed: Vec<String> },
+    /// An inline `=value` was given to an argument that doesn't accept a value
+    /// (e.g., `--verbose=true` where `verbose` is a flag).
+    UnexpectedValue(String),
 }
 
 impl Display for ArgParseError {
@@ -41,6 +46,16 @@ impl Display for ArgParseError {
             ArgParseError::MissingRequiredArgument(arg) => {
                 write!(f, "missing required argument '{}'", arg)
             }
+            ArgParseError::InvalidValue { option, value, allowed } => {
+                write!(
+                    f,
+                    "invalid value '{}' for option '{}' (possible values: {})",
+                    value, option, allowed.join(", ")
+                )
+            }
+            ArgParseError::UnexpectedValue(arg) => {
+                write!(f, "argument '{}' does not take a value", arg)
+            }
         }
     }
 }
@@ -50,7 +65,12 @@ struct Argument {
     pub short_name: Option<char>,
     pub long_name: String,
     pub help: String,
-    pub required: bool
+    pub required: bool,
+    pub multiple: bool,
+    pub counted: bool,
+    pub default_value: Option<String>,
+    pub env_name: Option<String>,
+    pub possible_values: Option<Vec<String>>
 }
 
 enum ArgumentKind {
@@ -70,7 +90,13 @@ pub struct ParsedArgs {
     /// A map of all options and their string values.
     pub options: HashMap<String, String>,
     /// A vector of all positional arguments in the order they appeared.
-    pub positional: Vec<String>
+    pub positional: Vec<String>,
+    /// A map of all multi-value options and the values they accumulated, in order.
+    pub option_lists: HashMap<String, Vec<String>>,
+    /// A map of all `counted` flags and how many times each was seen.
+    pub flag_counts: HashMap<String, u32>,
+    /// The subcommand that was invoked, if any, along with its own parsed arguments.
+    pub subcommand: Option<(String, Box<ParsedArgs>)>
 }
 
 impl ParsedArgs {
@@ -95,11 +121,68 @@ impl ParsedArgs {
         let option = self.options.get(name)?;
         Some(option.parse::<T>())
     }
+
+    /// Gets and parses all values of a `multiple` option into a specific type.
+    ///
+    /// This method attempts to find an accumulating option by its name and then
+    /// parse each of its string values into any type `T` that implements `FromStr`.
+    ///
+    /// # Returns
+    ///
+    /// * `None`: If the option was not provided by the user.
+    /// * `Some(values)`: A result per value, in the order they were provided.
+    pub fn get_values_as<T: FromStr>(&self, name: &str) -> Option<Vec<Result<T, T::Err>>> {
+        let values = self.option_lists.get(name)?;
+        Some(values.iter().map(|v| v.parse::<T>()).collect())
+    }
+
+    /// Gets the number of times a `counted` flag was seen. Returns `0` if it
+    /// was never provided.
+    pub fn count(&self, name: &str) -> u32 {
+        self.flag_counts.get(name).copied().unwrap_or(0)
+    }
 }
 
+/// The maximum width of the option/argument label column in help output,
+/// mirroring argparse's `OPTION_WIDTH`.
+const OPTION_WIDTH: usize = 24;
+
+/// The default total width of a help line, mirroring argparse's `TOTAL_WIDTH`.
+const TOTAL_WIDTH: usize = 79;
+
 /// The main parser object used to define arguments and run the parser.
 pub struct Parser {
-    definitions: Vec<Argument>
+    definitions: Vec<Argument>,
+    subcommands: Vec<(String, Parser)>,
+    help_width: usize
+}
+
+/// Wraps `text` into lines no wider than `width`, breaking on word boundaries.
+/// A single word longer than `width` is not split and stands alone on a line
+/// wider than `width`.
+///
+/// Returns an empty vector if `text` is empty.
+fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        if current.is_empty() {
+            current.push_str(word);
+        } else if current.len() + 1 + word.len() <= width {
+            current.push(' ');
+            current.push_str(word);
+        } else {
+            lines.push(std::mem::take(&mut current));
+            current.push_str(word);
+        }
+    }
+
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    lines
 }
 
 /// A temporary builder object for configuring arguments.
@@ -134,6 +217,53 @@ impl<'a> ArgumentBuilder<'a> {
         }
         self
     }
+
+    /// Marks an option as accumulating: every occurrence is collected instead of
+    /// overwriting the previous value.
+    ///
+    /// Values are stored in `ParsedArgs.option_lists` rather than `ParsedArgs.options`.
+    pub fn multiple(self) -> Self {
+        if let Some(arg) = self.parser.definitions.last_mut() {
+            arg.multiple = true;
+        }
+        self
+    }
+
+    /// Marks a flag as counted: each occurrence increments `ParsedArgs.flag_counts`
+    /// instead of only recording presence, enabling `-vvv`-style verbosity levels.
+    pub fn counted(self) -> Self {
+        if let Some(arg) = self.parser.definitions.last_mut() {
+            arg.counted = true;
+        }
+        self
+    }
+
+    /// Sets a default value used when the option is not provided on the command line.
+    pub fn with_default(self, default_value: &str) -> Self {
+        if let Some(arg) = self.parser.definitions.last_mut() {
+            arg.default_value = Some(default_value.to_string());
+        }
+        self
+    }
+
+    /// Falls back to the given environment variable when the option is not
+    /// provided on the command line. Takes priority over `with_default`.
+    pub fn from_env(self, env_name: &str) -> Self {
+        if let Some(arg) = self.parser.definitions.last_mut() {
+            arg.env_name = Some(env_name.to_string());
+        }
+        self
+    }
+
+    /// Restricts an option's value to one of the given choices.
+    ///
+    /// Any value outside this set is rejected with `ArgParseError::InvalidValue`.
+    pub fn possible_values(self, values: &[&str]) -> Self {
+        if let Some(arg) = self.parser.definitions.last_mut() {
+            arg.possible_values = Some(values.iter().map(|v| v.to_string()).collect());
+        }
+        self
+    }
 }
 
 
@@ -141,10 +271,22 @@ impl Parser {
     /// Creates a new, empty parser.
     pub fn new() -> Self {
         Parser {
-            definitions: Vec::new()
+            definitions: Vec::new(),
+            subcommands: Vec::new(),
+            help_width: TOTAL_WIDTH
         }
     }
 
+    /// Registers a named subcommand and returns its child `Parser` for configuration.
+    ///
+    /// When the first non-flag token on the command line matches `name`, the
+    /// remaining arguments are parsed by the returned child parser instead of
+    /// this one, and the result is exposed via `ParsedArgs.subcommand`.
+    pub fn add_subcommand(&mut self, name: &str) -> &mut Parser {
+        self.subcommands.push((name.to_string(), Parser::new()));
+        &mut self.subcommands.last_mut().unwrap().1
+    }
+
     fn add(
         &mut self,
         long_name: &str,
@@ -155,7 +297,12 @@ impl Parser {
             short_name: None,
             long_name: long_name.to_string(),
             help: "".to_string(),
-            required: false
+            required: false,
+            multiple: false,
+            counted: false,
+            default_value: None,
+            env_name: None,
+            possible_values: None
         })
     }
 
@@ -183,6 +330,12 @@ impl Parser {
         ArgumentBuilder{parser: self}
     }
 
+    /// Overrides the total help output width used to wrap option and argument
+    /// descriptions. Defaults to `TOTAL_WIDTH`.
+    pub fn set_help_width(&mut self, width: usize) {
+        self.help_width = width;
+    }
+
     /// Generates a formatted help message string based on the defined arguments.
     pub fn generate_help(&self) -> String {
 
@@ -193,26 +346,94 @@ impl Parser {
         help.push_str(&format!("Usage: {} [OPTIONS] [ARGUMENTS]\n", name));
         help.push_str("\nOptions:\n");
 
+        let option_labels: Vec<String> = self.definitions.iter()
+            .filter(|def| matches!(def.arg_type, ArgumentKind::Flag | ArgumentKind::Option))
+            .map(|def| {
+                let short = def.short_name.map_or_else(
+                    || "    ".to_string(),
+                    |s| format!("-{}, ", s)
+                );
+                format!("{}{}", short, def.long_name)
+            })
+            .collect();
+        let option_column = Self::label_column_width(&option_labels);
+
         for def in &self.definitions {
             if let ArgumentKind::Flag | ArgumentKind::Option = def.arg_type {
                 let short = def.short_name.map_or_else(
                     || "    ".to_string(),
                     |s| format!("-{}, ", s)
                 );
-                help.push_str(&format!("  {}{:<20} {}\n", short, def.long_name, def.help))
+                let label = format!("{}{}", short, def.long_name);
+                let help_text = format!("{}{}", def.help, Self::format_option_suffix(def));
+                self.push_help_entry(&mut help, &label, option_column, &help_text);
             }
         }
 
         help.push_str("\nArguments:\n");
+        let positional_labels: Vec<String> = self.definitions.iter()
+            .filter(|def| matches!(def.arg_type, ArgumentKind::Positional))
+            .map(|def| def.long_name.clone())
+            .collect();
+        let positional_column = Self::label_column_width(&positional_labels);
+
         for def in &self.definitions {
             if let ArgumentKind::Positional = def.arg_type {
-                help.push_str(&format!("  {:<22} {}\n", def.long_name, def.help))
+                self.push_help_entry(&mut help, &def.long_name, positional_column, &def.help);
+            }
+        }
+
+        if !self.subcommands.is_empty() {
+            help.push_str("\nCommands:\n");
+            for (name, _) in &self.subcommands {
+                help.push_str(&format!("  {}\n", name))
             }
         }
 
         help
     }
 
+    /// Builds the `[default: ...]`/`[env: ...]`/`[possible values: ...]` suffix
+    /// appended to an option's help text, or an empty string if none is set.
+    fn format_option_suffix(def: &Argument) -> String {
+        let mut suffix = String::new();
+        if let Some(default_value) = &def.default_value {
+            suffix.push_str(&format!(" [default: {}]", default_value));
+        }
+        if let Some(env_name) = &def.env_name {
+            suffix.push_str(&format!(" [env: {}]", env_name));
+        }
+        if let Some(allowed) = &def.possible_values {
+            suffix.push_str(&format!(" [possible values: {}]", allowed.join(", ")));
+        }
+        suffix
+    }
+
+    /// Computes a label column width from a set of labels, capped at `OPTION_WIDTH`.
+    fn label_column_width(labels: &[String]) -> usize {
+        labels.iter().map(|l| l.len()).max().unwrap_or(0).min(OPTION_WIDTH)
+    }
+
+    /// Appends one help entry (`  <label> <description>`, with the label padded
+    /// to `column`) to `help`, wrapping
+    /// the description to `self.help_width` and indenting continuation lines
+    /// so they align under the description column.
+    fn push_help_entry(&self, help: &mut String, label: &str, column: usize, description: &str) {
+        let indent_width = 2 + column + 1;
+        let desc_width = self.help_width.saturating_sub(indent_width).max(1);
+        let wrapped = wrap_text(description, desc_width);
+        let continuation_indent = " ".repeat(indent_width);
+
+        let mut lines = wrapped.into_iter();
+        match lines.next() {
+            Some(first) => help.push_str(&format!("  {:<column$} {}\n", label, first, column = column)),
+            None => help.push_str(&format!("  {}\n", label))
+        }
+        for line in lines {
+            help.push_str(&format!("{}{}\n", continuation_indent, line));
+        }
+    }
+
     /// Parses a given iterator of string arguments.
     ///
     /// This is the main entry point for the parser.
@@ -225,16 +446,44 @@ impl Parser {
         let mut results = ParsedArgs {
             flags: HashSet::new(),
             options: HashMap::new(),
-            positional: vec![]
+            positional: vec![],
+            option_lists: HashMap::new(),
+            flag_counts: HashMap::new(),
+            subcommand: None
         };
 
         args.next(); // skip program name
 
+        let mut positional_only = false;
+
         while let Some(arg) = args.next() {
+            if positional_only {
+                results.positional.push(arg);
+                continue;
+            }
+            if arg == "--" {
+                positional_only = true;
+                continue;
+            }
             if arg == "--help" || arg == "-h" {
                 return Err(ArgParseError::HelpRequested);
             }
             if let Some(arg_without_prefix) = arg.strip_prefix("--") {
+                if let Some((name, value)) = arg_without_prefix.split_once('=') {
+                    let argument_def = self.definitions
+                        .iter()
+                        .find(|x| x.long_name == name);
+                    match argument_def {
+                        None => {
+                            return Err(ArgParseError::UnknownArgument(String::from(name)))
+                        }
+                        Some(def) => {
+                            Self::store_inline_option_value(&mut results, def, value)?;
+                        }
+                    }
+
+                    continue;
+                }
                 let argument_def = self.definitions
                     .iter()
                     .find(|x| {
@@ -247,10 +496,11 @@ impl Parser {
                     Some(def) => {
                         match def.arg_type {
                             ArgumentKind::Flag => {
-                                results.flags.insert(def.long_name.clone());
+                                Self::record_flag(&mut results, def);
                             }
                             ArgumentKind::Option => {
-                                Self::extract_option(&mut args, &mut results, def)?
+                                let value = Self::extract_option(&mut args, def)?;
+                                Self::store_option_value(&mut results, def, value)?;
                             }
                             ArgumentKind::Positional => {
                                 unreachable!(
@@ -263,6 +513,25 @@ impl Parser {
 
 
             } else if let Some(arg_without_prefix) = arg.strip_prefix("-") {
+                if let Some((name, value)) = arg_without_prefix.split_once('=') {
+                    let mut chars = name.chars();
+                    let short_name = chars.next();
+                    let argument_def = if short_name.is_some() && chars.next().is_none() {
+                        self.definitions.iter().find(|x| x.short_name == short_name)
+                    } else {
+                        None
+                    };
+                    match argument_def {
+                        None => {
+                            return Err(ArgParseError::UnknownArgument(String::from(name)))
+                        }
+                        Some(def) => {
+                            Self::store_inline_option_value(&mut results, def, value)?;
+                        }
+                    }
+
+                    continue;
+                }
                 let count = arg_without_prefix.chars().count();
                 for (i, char) in arg_without_prefix.chars().enumerate() {
                     let argument_def = self.definitions
@@ -276,11 +545,12 @@ impl Parser {
                         Some(def) => {
                             match def.arg_type {
                                 ArgumentKind::Flag => {
-                                    results.flags.insert(def.long_name.clone());
+                                    Self::record_flag(&mut results, def);
                                 }
                                 ArgumentKind::Option => {
                                     if i == count - 1 {
-                                        Self::extract_option(&mut args, &mut results, def)?;
+                                        let value = Self::extract_option(&mut args, def)?;
+                                        Self::store_option_value(&mut results, def, value)?;
                                     } else {
                                         return Err(ArgParseError::OptionInMiddleOfGroup(def.long_name.clone()));
                                     }
@@ -295,21 +565,64 @@ impl Parser {
                         }
                     }
                 }
+            } else if results.subcommand.is_none() && results.positional.is_empty()
+                && self.subcommands.iter().any(|(name, _)| name == &arg) {
+                let (name, sub_parser) = self.subcommands.iter().find(|(name, _)| name == &arg).unwrap();
+                let rest: Vec<String> = args.by_ref().collect();
+                let sub_args = std::iter::once(name.clone()).chain(rest);
+                let sub_result = sub_parser.parse(sub_args)?;
+                results.subcommand = Some((name.clone(), Box::new(sub_result)));
+                break;
             } else {
                 results.positional.push(arg)
             }
         }
 
+        self.apply_defaults(&mut results)?;
+
         // validate any required parameters
         self.validate_args(&results)?;
         Ok(results)
     }
 
+    /// Fills in any `Option` arguments that weren't provided on the command line,
+    /// preferring an environment variable (`from_env`) over a literal `with_default`.
+    ///
+    /// Goes through `store_option_value` so a fallback value is still subject to
+    /// `possible_values` validation and still lands in `option_lists` for
+    /// `multiple` options.
+    fn apply_defaults(&self, results: &mut ParsedArgs) -> Result<(), ArgParseError> {
+        for def in &self.definitions {
+            if !matches!(def.arg_type, ArgumentKind::Option) {
+                continue;
+            }
+
+            let already_provided = if def.multiple {
+                results.option_lists.contains_key(&def.long_name)
+            } else {
+                results.options.contains_key(&def.long_name)
+            };
+            if already_provided {
+                continue;
+            }
+
+            let fallback = def.env_name.as_deref()
+                .and_then(|name| std::env::var(name).ok())
+                .or_else(|| def.default_value.clone());
+
+            if let Some(value) = fallback {
+                Self::store_option_value(results, def, value)?;
+            }
+        }
+        Ok(())
+    }
+
     fn validate_args(&self, results: &ParsedArgs) -> Result<(), ArgParseError> {
         for def in &self.definitions {
             if def.required {
                 let was_provided = match def.arg_type {
                     ArgumentKind::Flag => results.flags.contains(&def.long_name),
+                    ArgumentKind::Option if def.multiple => results.option_lists.contains_key(&def.long_name),
                     ArgumentKind::Option => results.options.contains_key(&def.long_name),
                     ArgumentKind::Positional => !results.positional.is_empty(),
                 };
@@ -322,17 +635,63 @@ impl Parser {
         Ok(())
     }
 
-    fn extract_option<T: Iterator<Item=String>>(args: &mut T, results: &mut ParsedArgs, x: &Argument) -> Result<(), ArgParseError> {
+    fn extract_option<T: Iterator<Item=String>>(args: &mut T, x: &Argument) -> Result<String, ArgParseError> {
         match args.next() {
-            Some(value) => {
-                results.options.insert(x.long_name.clone(), value);
-                Ok(())
-            }
+            Some(value) => Ok(value),
             None => {
                 Err(ArgParseError::MissingValueForOption(x.long_name.clone()))
             }
         }
     }
+
+    /// Records a flag occurrence, incrementing `flag_counts` if the flag was
+    /// marked `counted`, in addition to the usual presence bit in `flags`.
+    fn record_flag(results: &mut ParsedArgs, x: &Argument) {
+        results.flags.insert(x.long_name.clone());
+        if x.counted {
+            *results.flag_counts.entry(x.long_name.clone()).or_insert(0) += 1;
+        }
+    }
+
+    /// Records a value for an option, accumulating it in `option_lists` if the
+    /// option was marked `multiple`, or overwriting `options` otherwise.
+    ///
+    /// Rejects the value with `InvalidValue` if the option has `possible_values`
+    /// and the value isn't one of them.
+    fn store_option_value(results: &mut ParsedArgs, x: &Argument, value: String) -> Result<(), ArgParseError> {
+        if let Some(allowed) = &x.possible_values {
+            if !allowed.contains(&value) {
+                return Err(ArgParseError::InvalidValue {
+                    option: x.long_name.clone(),
+                    value,
+                    allowed: allowed.clone()
+                });
+            }
+        }
+
+        if x.multiple {
+            results.option_lists.entry(x.long_name.clone()).or_default().push(value);
+        } else {
+            results.options.insert(x.long_name.clone(), value);
+        }
+        Ok(())
+    }
+
+    /// Handles the `--opt=value`/`-o=value` inline form: validates that the
+    /// matched definition actually takes a value and that one was provided.
+    fn store_inline_option_value(results: &mut ParsedArgs, x: &Argument, value: &str) -> Result<(), ArgParseError> {
+        match x.arg_type {
+            ArgumentKind::Option => {
+                if value.is_empty() {
+                    return Err(ArgParseError::MissingValueForOption(x.long_name.clone()));
+                }
+                Self::store_option_value(results, x, value.to_string())
+            }
+            ArgumentKind::Flag | ArgumentKind::Positional => {
+                Err(ArgParseError::UnexpectedValue(x.long_name.clone()))
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -488,15 +847,20 @@ mod tests {
         let help_text = parser.generate_help();
 
         // Use a raw string literal r#"..."# for easy multi-line comparison
-        let expected_text = r#"Usage: [PROGRAM_NAME] [OPTIONS] [ARGUMENTS]
+        let name = env!("CARGO_PKG_NAME");
+        let version = env!("CARGO_PKG_VERSION");
+        let expected_text = format!(
+            r#"{name} {version}
+Usage: {name} [OPTIONS] [ARGUMENTS]
 
 Options:
-  -a, all                  List all items.
-  -o, output               Specify output file.
+  -a, all    List all items.
+  -o, output Specify output file.
 
 Arguments:
-  input                  The input file to process.
-"#;
+  input The input file to process.
+"#
+        );
         assert_eq!(help_text, expected_text);
     }
 
@@ -516,6 +880,272 @@ Arguments:
         assert!(matches!(result, Err(ArgParseError::MissingRequiredArgument(_))));
     }
 
+    #[test]
+    fn test_required_multiple_option_is_satisfied_by_repeated_values() {
+        let mut parser = Parser::new();
+        parser.add_option("include").multiple().required();
+        let result = parser.parse(to_args(vec!["program", "--include", "a", "--include", "b"]));
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_err_missing_required_multiple_option() {
+        let mut parser = Parser::new();
+        parser.add_option("include").multiple().required();
+        let result = parser.parse(to_args(vec!["program"]));
+        assert!(matches!(result, Err(ArgParseError::MissingRequiredArgument(_))));
+    }
+
+    #[test]
+    fn test_wrap_text_breaks_on_word_boundaries() {
+        let wrapped = wrap_text("the quick brown fox jumps", 10);
+        assert_eq!(wrapped, vec!["the quick", "brown fox", "jumps"]);
+    }
+
+    #[test]
+    fn test_wrap_text_empty_input() {
+        assert_eq!(wrap_text("", 10), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_generate_help_wraps_long_description_with_aligned_continuation() {
+        let mut parser = Parser::new();
+        parser.set_help_width(30);
+        parser.add_flag("all")
+            .with_short_name('a')
+            .with_help("List every single item, including hidden ones.");
+
+        let help_text = parser.generate_help();
+        let options_section = help_text.split("Options:\n").nth(1).unwrap();
+        let lines: Vec<&str> = options_section.lines().take(3).collect();
+
+        assert_eq!(lines[0], "  -a, all List every single");
+        assert_eq!(lines[1], "          item, including");
+        assert_eq!(lines[2], "          hidden ones.");
+    }
+
+    #[test]
+    fn test_subcommand_dispatches_remaining_args() {
+        let mut parser = Parser::new();
+        parser.add_flag("verbose").with_short_name('v');
+        parser.add_subcommand("add")
+            .add_positional("file")
+            .required();
+
+        let result = parser.parse(to_args(vec!["program", "-v", "add", "file.txt"])).unwrap();
+        assert!(result.flags.contains("verbose"));
+        let (name, sub) = result.subcommand.unwrap();
+        assert_eq!(name, "add");
+        assert_eq!(sub.positional, vec!["file.txt"]);
+    }
+
+    #[test]
+    fn test_no_subcommand_leaves_field_none() {
+        let mut parser = Parser::new();
+        parser.add_subcommand("add");
+        let result = parser.parse(to_args(vec!["program"])).unwrap();
+        assert!(result.subcommand.is_none());
+    }
+
+    #[test]
+    fn test_unmatched_positional_is_not_treated_as_subcommand() {
+        let mut parser = Parser::new();
+        parser.add_positional("input");
+        parser.add_subcommand("add");
+        let result = parser.parse(to_args(vec!["program", "data.csv"])).unwrap();
+        assert!(result.subcommand.is_none());
+        assert_eq!(result.positional, vec!["data.csv"]);
+    }
+
+    #[test]
+    fn test_possible_values_accepts_allowed_value() {
+        let mut parser = Parser::new();
+        parser.add_option("format").possible_values(&["json", "yaml", "toml"]);
+        let result = parser.parse(to_args(vec!["program", "--format", "yaml"])).unwrap();
+        assert_eq!(result.options.get("format"), Some(&"yaml".to_string()));
+    }
+
+    #[test]
+    fn test_possible_values_rejects_disallowed_value() {
+        let mut parser = Parser::new();
+        parser.add_option("format").possible_values(&["json", "yaml", "toml"]);
+        let result = parser.parse(to_args(vec!["program", "--format", "xml"]));
+        assert!(matches!(result, Err(ArgParseError::InvalidValue { .. })));
+    }
+
+    #[test]
+    fn test_possible_values_rejects_disallowed_inline_value() {
+        let mut parser = Parser::new();
+        parser.add_option("format").possible_values(&["json", "yaml"]);
+        let result = parser.parse(to_args(vec!["program", "--format=xml"]));
+        assert!(matches!(result, Err(ArgParseError::InvalidValue { .. })));
+    }
+
+    #[test]
+    fn test_possible_values_rejects_disallowed_default_value() {
+        let mut parser = Parser::new();
+        parser.add_option("format").possible_values(&["json", "yaml"]).with_default("bogus");
+        let result = parser.parse(to_args(vec!["program"]));
+        assert!(matches!(result, Err(ArgParseError::InvalidValue { .. })));
+    }
+
+    #[test]
+    fn test_option_default_value_is_used_when_absent() {
+        let mut parser = Parser::new();
+        parser.add_option("port").with_default("8080");
+        let result = parser.parse(to_args(vec!["program"])).unwrap();
+        assert_eq!(result.options.get("port"), Some(&"8080".to_string()));
+    }
+
+    #[test]
+    fn test_option_env_fallback_takes_priority_over_default() {
+        // SAFETY: no other test in this crate reads or writes environment
+        // variables, and this variable name is used only here.
+        unsafe { std::env::set_var("SARPA_TEST_PORT", "9090"); }
+        let mut parser = Parser::new();
+        parser.add_option("port").with_default("8080").from_env("SARPA_TEST_PORT");
+        let result = parser.parse(to_args(vec!["program"])).unwrap();
+        assert_eq!(result.options.get("port"), Some(&"9090".to_string()));
+        unsafe { std::env::remove_var("SARPA_TEST_PORT"); }
+    }
+
+    #[test]
+    fn test_required_option_satisfied_by_default_value() {
+        let mut parser = Parser::new();
+        parser.add_option("port").required().with_default("8080");
+        let result = parser.parse(to_args(vec!["program"]));
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_explicit_value_overrides_default() {
+        let mut parser = Parser::new();
+        parser.add_option("port").with_default("8080");
+        let result = parser.parse(to_args(vec!["program", "--port", "1234"])).unwrap();
+        assert_eq!(result.options.get("port"), Some(&"1234".to_string()));
+    }
+
+    #[test]
+    fn test_multiple_option_default_lands_in_option_lists() {
+        let mut parser = Parser::new();
+        parser.add_option("include").multiple().with_default("x");
+        let result = parser.parse(to_args(vec!["program"])).unwrap();
+        assert_eq!(result.option_lists.get("include"), Some(&vec!["x".to_string()]));
+        assert!(result.get_values_as::<String>("include").is_some());
+    }
+
+    #[test]
+    fn test_counted_flag_short_group() {
+        let mut parser = Parser::new();
+        parser.add_flag("verbose")
+            .with_short_name('v')
+            .with_help("increases the verbosity")
+            .counted();
+        let result = parser.parse(to_args(vec!["program", "-vvv"])).unwrap();
+        assert!(result.flags.contains("verbose"));
+        assert_eq!(result.count("verbose"), 3);
+    }
+
+    #[test]
+    fn test_counted_flag_repeated_long_form() {
+        let mut parser = Parser::new();
+        parser.add_flag("verbose")
+            .with_short_name('v')
+            .with_help("increases the verbosity")
+            .counted();
+        let result = parser.parse(to_args(vec!["program", "--verbose", "--verbose"])).unwrap();
+        assert_eq!(result.count("verbose"), 2);
+    }
+
+    #[test]
+    fn test_non_counted_flag_has_no_count() {
+        let mut parser = Parser::new();
+        parser.add_flag("verbose")
+            .with_short_name('v')
+            .with_help("increases the verbosity");
+        let result = parser.parse(to_args(vec!["program", "-v"])).unwrap();
+        assert!(result.flags.contains("verbose"));
+        assert_eq!(result.count("verbose"), 0);
+    }
+
+    #[test]
+    fn test_end_of_options_delimiter() {
+        let mut parser = Parser::new();
+        parser.add_flag("verbose")
+            .with_short_name('v')
+            .with_help("test");
+        parser.add_positional("files")
+            .with_help("test");
+        let result = parser.parse(to_args(vec!["program", "-v", "--", "--weird-file", "-v"])).unwrap();
+        assert!(result.flags.contains("verbose"));
+        assert_eq!(result.positional, vec!["--weird-file", "-v"]);
+    }
+
+    #[test]
+    fn test_long_option_inline_value() {
+        let mut parser = Parser::new();
+        parser.add_option("output")
+            .with_short_name('o')
+            .with_help("where the output should be stored");
+        let result = parser.parse(to_args(vec!["program", "--output=file.txt"])).unwrap();
+        assert_eq!(result.options.get("output"), Some(&"file.txt".to_string()));
+    }
+
+    #[test]
+    fn test_short_option_inline_value() {
+        let mut parser = Parser::new();
+        parser.add_option("output")
+            .with_short_name('o')
+            .with_help("where the output should be stored");
+        let result = parser.parse(to_args(vec!["program", "-o=file.txt"])).unwrap();
+        assert_eq!(result.options.get("output"), Some(&"file.txt".to_string()));
+    }
+
+    #[test]
+    fn test_err_long_option_inline_value_empty() {
+        let mut parser = Parser::new();
+        parser.add_option("output")
+            .with_short_name('o')
+            .with_help("test");
+        let result = parser.parse(to_args(vec!["program", "--output="]));
+        assert!(matches!(result, Err(ArgParseError::MissingValueForOption(_))));
+    }
+
+    #[test]
+    fn test_err_inline_value_on_flag_is_unexpected_value() {
+        let mut parser = Parser::new();
+        parser.add_flag("verbose")
+            .with_short_name('v')
+            .with_help("test");
+        let result = parser.parse(to_args(vec!["program", "--verbose=true"]));
+        assert!(matches!(result, Err(ArgParseError::UnexpectedValue(_))));
+    }
+
+    #[test]
+    fn test_multiple_option_accumulates_values() {
+        let mut parser = Parser::new();
+        parser.add_option("include")
+            .with_short_name('i')
+            .with_help("paths to include")
+            .multiple();
+        let result = parser.parse(to_args(vec!["program", "--include", "a", "-i", "b"])).unwrap();
+        assert_eq!(result.option_lists.get("include"), Some(&vec!["a".to_string(), "b".to_string()]));
+        assert!(!result.options.contains_key("include"));
+    }
+
+    #[test]
+    fn test_get_values_as() {
+        let mut parser = Parser::new();
+        parser.add_option("port")
+            .multiple();
+        let result = parser.parse(to_args(vec!["program", "--port", "80", "--port", "443"])).unwrap();
+
+        let values = result.get_values_as::<u32>("port").unwrap();
+        assert_eq!(values, vec![Ok(80), Ok(443)]);
+
+        assert!(result.get_values_as::<u32>("missing").is_none());
+    }
+
     #[test]
     fn test_get_value_as() {
 
@@ -527,6 +1157,9 @@ Arguments:
             flags: HashSet::new(),
             options,
             positional: vec![],
+            option_lists: HashMap::new(),
+            flag_counts: HashMap::new(),
+            subcommand: None,
         };
 
         // 2. Test Success case: Valid key and valid parse